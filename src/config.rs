@@ -0,0 +1,50 @@
+//! Runtime configuration, loaded from the environment.
+//!
+//! Centralises the values that used to be hardcoded — the short domain and the
+//! bind address — so the service can run behind a real domain without code
+//! changes. `shorten` builds short URLs from the configured base and `redirect`
+//! reconstructs them the same way.
+use crate::store::StorageBackend;
+
+/// Service configuration threaded through `web::Data`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// URL scheme used when building short URLs, e.g. `https`.
+    pub scheme: String,
+    /// Short domain the service advertises, e.g. `short.fe`.
+    pub domain: String,
+    /// Host the HTTP server binds to.
+    pub bind_host: String,
+    /// Port the HTTP server binds to.
+    pub bind_port: u16,
+    /// Which storage backend to construct.
+    pub storage: StorageBackend,
+}
+
+impl Config {
+    /// Loads configuration from the environment, falling back to the historical
+    /// defaults (`http://short.fe` and `127.0.0.1:8000`).
+    pub fn from_env() -> Self {
+        Config {
+            scheme: std::env::var("SHORT_IRON_SCHEME").unwrap_or_else(|_| "http".to_string()),
+            domain: std::env::var("SHORT_IRON_DOMAIN").unwrap_or_else(|_| "short.fe".to_string()),
+            bind_host: std::env::var("SHORT_IRON_HOST")
+                .unwrap_or_else(|_| "127.0.0.1".to_string()),
+            bind_port: std::env::var("SHORT_IRON_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(8000),
+            storage: StorageBackend::from_env(),
+        }
+    }
+
+    /// Builds the full short URL for a given ID, e.g. `http://short.fe/abc123`.
+    pub fn short_url(&self, id: &str) -> String {
+        format!("{}://{}/{}", self.scheme, self.domain, id)
+    }
+
+    /// The `host:port` address the HTTP server should bind to.
+    pub fn bind_address(&self) -> String {
+        format!("{}:{}", self.bind_host, self.bind_port)
+    }
+}
@@ -1,8 +1,10 @@
 mod debugger;
 mod redirect;
 mod shorten_url;
+mod stats;
 
 // re-export the handlers here to avoid repetitive 'use' statements:
 pub use debugger::debugger;
 pub use redirect::redirect;
 pub use shorten_url::shorten;
+pub use stats::stats;
@@ -1,10 +1,26 @@
 use actix_web::{error, Result, web};
-use error::ErrorBadRequest;
+use error::{ErrorBadGateway, ErrorBadRequest, ErrorConflict};
 use nanoid::nanoid;
 use tracing::{debug, error, info};
 use url::Url;
 
-use crate::{KnownUrls, UrlRequest};
+use crate::{
+    config::Config,
+    provider::ShortenerClient,
+    store::{Resolved, Store},
+    UrlRequest,
+};
+
+/// Characters permitted in a user-supplied vanity alias.
+///
+/// Matches the URL-safe set `nanoid` draws from, so generated and custom IDs
+/// share the same shape.
+fn is_valid_alias(alias: &str) -> bool {
+    !alias.is_empty()
+        && alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
 
 /// Handles POST requests to shorten URLs.
 ///
@@ -17,7 +33,9 @@ use crate::{KnownUrls, UrlRequest};
 /// Returns a shortened URL or a [`BadRequest`](error::ErrorBadRequest)
 pub async fn shorten(
     url_req: web::Json<UrlRequest>,
-    known_urls: web::Data<KnownUrls>,
+    known_urls: web::Data<dyn Store>,
+    shortener: web::Data<Option<ShortenerClient>>,
+    config: web::Data<Config>,
 ) -> Result<String> {
     let submitted_url = &url_req.url.to_string();
     let valid_url = match Url::parse(submitted_url) {
@@ -34,27 +52,66 @@ pub async fn shorten(
         }
     };
 
-    let mut urls = known_urls.urls.write().await;
-    debug!(?urls, "Obtained write lock to known URLs");
+    // when an outbound provider is configured, delegate to it instead of
+    // minting a short.fe/... ID locally
+    if let Some(client) = shortener.get_ref() {
+        return match client.shorten(valid_url.as_str()).await {
+            Ok(shortened) => {
+                info!(shortened_url = shortened.as_str(), "Delegated to provider");
+                Ok(shortened)
+            }
+            Err(e) => {
+                error!(error = ?e, "Provider failed to shorten URL");
+                Err(ErrorBadGateway(e))
+            }
+        };
+    }
 
     // check if the value already exists before inserting the value, calling
     // insert and using the returned Option would change the shortened URL
-    match urls.get(&valid_url.to_string()) {
+    match known_urls.get(&valid_url.to_string()).await {
         Some(existing) => {
             debug!(
                 shortened_url = ?existing,
                 "Submitted URL already shortened."
             );
-            Ok(existing.into())
+            Ok(existing)
         }
         None => {
             debug!(
                 url = ?submitted_url,
                 "URL not yet recorded, generating ID"
             );
-            let shortened = format!("short.fe/{}", nanoid!(10));
 
-            urls.insert(valid_url.to_string(), shortened.to_string());
+            // honour a requested vanity alias when present, otherwise fall back
+            // to a randomly generated ID
+            let shortened = match &url_req.alias {
+                Some(alias) => {
+                    if !is_valid_alias(alias) {
+                        error!(?alias, "Requested alias contains invalid characters");
+                        return Err(ErrorBadRequest(
+                            "alias may only contain alphanumerics, '-' and '_'",
+                        ));
+                    }
+
+                    let candidate = config.short_url(alias);
+                    if !matches!(known_urls.resolve(&candidate).await, Resolved::Missing) {
+                        error!(?candidate, "Requested alias is already taken");
+                        return Err(ErrorConflict("alias already in use"));
+                    }
+
+                    candidate
+                }
+                None => config.short_url(&nanoid!(10)),
+            };
+
+            known_urls
+                .insert(
+                    valid_url.to_string(),
+                    shortened.to_string(),
+                    url_req.expiry_deadline(),
+                )
+                .await;
 
             info!(
                 shortened_url = shortened.as_str(),
@@ -0,0 +1,30 @@
+use actix_web::{Responder, web};
+use tracing::{debug, info};
+use web::Json;
+
+use crate::config::Config;
+use crate::store::Store;
+
+/// Responds with the access statistics for a single short URL.
+///
+/// Returns JSON describing the long URL, the short URL, and its hit count, or
+/// a 404 Not Found if the short URL is unknown.
+pub async fn stats(
+    short_url_id: web::Path<String>,
+    known_urls: web::Data<dyn Store>,
+    config: web::Data<Config>,
+) -> impl Responder {
+    let short_url = config.short_url(&short_url_id.0);
+    debug!(?short_url, "Looking up stats for short URL");
+
+    match known_urls.stats(&short_url).await {
+        Some(stats) => {
+            info!(?short_url, hits = stats.hits, "Returned stats for short URL");
+            Ok(Json(stats))
+        }
+        None => {
+            info!(?short_url, "No stats, short URL isn't registered");
+            Err(actix_web::error::ErrorNotFound("unknown short URL"))
+        }
+    }
+}
@@ -18,6 +18,11 @@
 //! - GET Request
 //! - Returns all known URLs and short versions in JSON format
 //!
+//! ## `/misc/stats/{short_url_id}`
+//! - GET Request
+//! - Returns the long URL, full short URL, hit count, and last access time for
+//!   a short URL as JSON
+//!
 //! # Logging
 //!
 //! Logging in this project relies on the `tracing` crate. Set the environment
@@ -34,23 +39,54 @@
 //! ./short-iron | bunyan -o short
 //! ```
 use std::collections::HashMap;
+use std::time::Duration;
 
 use actix_web::{App, HttpServer, web};
 use async_std::sync::RwLock;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use tracing::{debug, info};
 use tracing_actix_web::TracingLogger;
 
-use handlers::{debugger, redirect, shorten};
+use config::Config;
+use handlers::{debugger, redirect, shorten, stats};
 use logging::*;
+use provider::ShortenerClient;
+use store::{Resolved, Store, UrlStats};
 
+mod config;
 mod handlers;
 mod logging;
+mod provider;
+mod store;
 
 /// Wraps a `String` type for POST requests to shorten URLs.
+///
+/// An optional `alias` can be supplied to request a memorable vanity ID
+/// instead of a randomly generated one, e.g.
+/// `{"url": "https://google.com", "alias": "goog"}`.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UrlRequest {
     url: String,
+    #[serde(default)]
+    alias: Option<String>,
+    /// Absolute expiry deadline in epoch seconds.
+    #[serde(default)]
+    expires_at: Option<u64>,
+    /// Relative lifetime in seconds, applied from the moment of insertion.
+    #[serde(default)]
+    ttl_seconds: Option<u64>,
+}
+
+impl UrlRequest {
+    /// Resolves the requested expiry to an absolute epoch-second deadline.
+    ///
+    /// An explicit `expires_at` wins; otherwise a `ttl_seconds` duration is
+    /// added to the current time. `None` means the link never expires.
+    fn expiry_deadline(&self) -> Option<u64> {
+        self.expires_at
+            .or_else(|| self.ttl_seconds.map(|ttl| store::now_epoch() + ttl))
+    }
 }
 
 /// Aliases for `String` for code clarity.
@@ -59,11 +95,110 @@ pub type ShortUrl = String;
 /// Aliases for `String` for code clarity.
 pub type LongURL = String;
 
-/// Wraps a `Mutex` around a `HashMap` for storing URLs and their shortened
-/// variants.
-#[derive(Debug)]
+/// A resolved mapping plus its optional expiry deadline.
+#[derive(Debug, Clone)]
+struct Record {
+    /// The long URL this short link expands to.
+    long: LongURL,
+    /// Absolute expiry deadline in epoch seconds, if the link is temporary.
+    expiry: Option<u64>,
+    /// Number of times the short link has been resolved.
+    hits: u64,
+    /// Epoch seconds of the most recent resolution, if any.
+    last_access: Option<u64>,
+}
+
+/// Both lookup directions for the URL map, kept together so a single lock
+/// keeps them consistent.
+#[derive(Debug, Default)]
+struct UrlMaps {
+    /// Long URL -> short URL, used to deduplicate in `shorten`.
+    long_to_short: HashMap<LongURL, ShortUrl>,
+    /// Short URL -> record, used for O(1) lookups in `redirect`.
+    short_to_long: HashMap<ShortUrl, Record>,
+}
+
+/// Wraps a `RwLock` around a dual `HashMap` for storing URLs and their
+/// shortened variants in memory, indexed in both directions.
+#[derive(Debug, Default)]
 pub struct KnownUrls {
-    urls: RwLock<HashMap<LongURL, ShortUrl>>,
+    urls: RwLock<UrlMaps>,
+}
+
+#[async_trait]
+impl Store for KnownUrls {
+    async fn get(&self, long: &LongURL) -> Option<ShortUrl> {
+        let urls = self.urls.read().await;
+        debug!("Obtained read lock to known URLs");
+        urls.long_to_short.get(long).cloned()
+    }
+
+    async fn resolve(&self, short: &ShortUrl) -> Resolved {
+        let urls = self.urls.read().await;
+        debug!("Obtained read lock to known URLs");
+        match urls.short_to_long.get(short) {
+            Some(record) => match record.expiry {
+                Some(deadline) if store::now_epoch() >= deadline => Resolved::Expired,
+                _ => Resolved::Found(record.long.clone()),
+            },
+            None => Resolved::Missing,
+        }
+    }
+
+    async fn insert(&self, long: LongURL, short: ShortUrl, expiry: Option<u64>) {
+        let mut urls = self.urls.write().await;
+        debug!("Obtained write lock to known URLs");
+        urls.long_to_short.insert(long.clone(), short.clone());
+        urls.short_to_long.insert(
+            short,
+            Record {
+                long,
+                expiry,
+                hits: 0,
+                last_access: None,
+            },
+        );
+    }
+
+    async fn record_hit(&self, short: &ShortUrl) {
+        let mut urls = self.urls.write().await;
+        if let Some(record) = urls.short_to_long.get_mut(short) {
+            record.hits += 1;
+            record.last_access = Some(store::now_epoch());
+        }
+    }
+
+    async fn stats(&self, short: &ShortUrl) -> Option<UrlStats> {
+        let urls = self.urls.read().await;
+        urls.short_to_long.get(short).map(|record| UrlStats {
+            long_url: record.long.clone(),
+            short_url: short.clone(),
+            hits: record.hits,
+            last_access: record.last_access,
+        })
+    }
+
+    async fn sweep(&self) -> usize {
+        let now = store::now_epoch();
+        let mut urls = self.urls.write().await;
+        let expired: Vec<ShortUrl> = urls
+            .short_to_long
+            .iter()
+            .filter(|(_, record)| matches!(record.expiry, Some(deadline) if now >= deadline))
+            .map(|(short, _)| short.clone())
+            .collect();
+
+        for short in &expired {
+            if let Some(record) = urls.short_to_long.remove(short) {
+                urls.long_to_short.remove(&record.long);
+            }
+        }
+        expired.len()
+    }
+
+    async fn all(&self) -> HashMap<LongURL, ShortUrl> {
+        self.urls.read().await.long_to_short.clone()
+    }
 }
 
 /// Sets up the HttpServer and shared resources.
@@ -77,20 +212,52 @@ async fn main() -> std::io::Result<()> {
     let subscriber = get_subscriber("short-iron".into(), "info".into());
     init_subscriber(subscriber);
 
-    let known_urls = web::Data::new(KnownUrls {
-        urls: RwLock::new(HashMap::new()),
+    let config = Config::from_env();
+    debug!(?config, "Loaded configuration from environment");
+    let store = config.storage.build()?;
+    debug!("Allocated store for known URLs");
+
+    // periodically sweep expired links so the store does not grow unbounded
+    let sweep_interval = Duration::from_secs(
+        std::env::var("SWEEP_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60),
+    );
+    let sweeper = store.clone();
+    async_std::task::spawn(async move {
+        loop {
+            async_std::task::sleep(sweep_interval).await;
+            let removed = sweeper.sweep().await;
+            if removed > 0 {
+                info!(removed, "Swept expired short URLs");
+            }
+        }
     });
-    debug!("Allocated RwLock and HashMap for known URLs");
+
+    let store: web::Data<dyn Store> = web::Data::from(store);
+
+    let shortener = web::Data::new(ShortenerClient::from_env());
+    debug!(
+        configured = shortener.is_some(),
+        "Configured outbound shortener client"
+    );
+
+    let bind_address = config.bind_address();
+    let config = web::Data::new(config);
 
     HttpServer::new(move || {
         App::new()
             .route("/shorten", web::post().to(shorten))
             .route("/{redirect_id}", web::get().to(redirect))
             .route("/misc/debug", web::get().to(debugger))
+            .route("/misc/stats/{short_url_id}", web::get().to(stats))
             .wrap(TracingLogger)
-            .app_data(known_urls.to_owned())
+            .app_data(store.to_owned())
+            .app_data(shortener.to_owned())
+            .app_data(config.to_owned())
     })
-    .bind("127.0.0.1:8000")?
+    .bind(bind_address)?
     .run()
     .await
 }
@@ -0,0 +1,137 @@
+//! Outbound shortening client.
+//!
+//! When a [`Provider`] is configured, `shorten` proxies requests to a
+//! third-party shortener rather than minting `short.fe/...` IDs locally. This
+//! lets operators reuse an existing short domain. With no provider configured
+//! the service falls back to its own generator.
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::debug;
+
+/// A supported third-party URL shortener.
+#[derive(Debug, Clone)]
+pub enum Provider {
+    /// <https://is.gd>
+    IsGd,
+    /// <https://v.gd>
+    VGd,
+    /// <https://tinyurl.com>
+    TinyUrl,
+    /// <https://bitly.com>, authenticated with a generic access token.
+    BitLy { token: String },
+}
+
+/// Shape of the relevant part of a Bitly `/v4/shorten` response.
+#[derive(Debug, Deserialize)]
+struct BitlyResponse {
+    link: String,
+}
+
+/// A `reqwest`-backed client that delegates shortening to a [`Provider`].
+#[derive(Debug, Clone)]
+pub struct ShortenerClient {
+    http: reqwest::Client,
+    provider: Provider,
+}
+
+impl ShortenerClient {
+    /// Builds a client for `provider` with the given request `timeout`.
+    pub fn new(provider: Provider, timeout: Duration) -> reqwest::Result<Self> {
+        let http = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self { http, provider })
+    }
+
+    /// Reads the desired provider from the environment and builds a client.
+    ///
+    /// `SHORTENER_PROVIDER` selects `isgd`, `vgd`, `tinyurl` or `bitly` (the
+    /// latter also requiring `BITLY_TOKEN`); anything else leaves the client
+    /// unset so the local generator is used. `SHORTENER_TIMEOUT_SECS` tunes the
+    /// request timeout (default 5).
+    pub fn from_env() -> Option<Self> {
+        let provider = match std::env::var("SHORTENER_PROVIDER").as_deref() {
+            Ok("isgd") => Provider::IsGd,
+            Ok("vgd") => Provider::VGd,
+            Ok("tinyurl") => Provider::TinyUrl,
+            Ok("bitly") => match std::env::var("BITLY_TOKEN") {
+                Ok(token) => Provider::BitLy { token },
+                Err(_) => {
+                    debug!("SHORTENER_PROVIDER=bitly set without BITLY_TOKEN, ignoring");
+                    return None;
+                }
+            },
+            _ => return None,
+        };
+
+        let timeout = Duration::from_secs(
+            std::env::var("SHORTENER_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+        );
+
+        match Self::new(provider, timeout) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                debug!(error = ?e, "Failed to build outbound shortener client");
+                None
+            }
+        }
+    }
+
+    /// Shortens `url` via the configured provider, returning the short link.
+    pub async fn shorten(&self, url: &str) -> reqwest::Result<String> {
+        match &self.provider {
+            Provider::IsGd => self.shorten_simple("https://is.gd/create.php", url).await,
+            Provider::VGd => self.shorten_simple("https://v.gd/create.php", url).await,
+            Provider::TinyUrl => {
+                self.shorten_tinyurl("https://tinyurl.com/api-create.php", url)
+                    .await
+            }
+            Provider::BitLy { token } => self.shorten_bitly(token, url).await,
+        }
+    }
+
+    /// Handles the is.gd/v.gd `create.php?format=simple` plain-text API.
+    async fn shorten_simple(&self, endpoint: &str, url: &str) -> reqwest::Result<String> {
+        let short = self
+            .http
+            .get(endpoint)
+            .query(&[("format", "simple"), ("url", url)])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(short.trim().to_string())
+    }
+
+    /// Handles the TinyUrl `api-create.php` plain-text API.
+    async fn shorten_tinyurl(&self, endpoint: &str, url: &str) -> reqwest::Result<String> {
+        let short = self
+            .http
+            .get(endpoint)
+            .query(&[("url", url)])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(short.trim().to_string())
+    }
+
+    /// Handles the Bitly `/v4/shorten` JSON API, authenticated by `token`.
+    async fn shorten_bitly(&self, token: &str, url: &str) -> reqwest::Result<String> {
+        let response: BitlyResponse = self
+            .http
+            .post("https://api-ssl.bitly.com/v4/shorten")
+            .bearer_auth(token)
+            .json(&serde_json::json!({ "long_url": url }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.link)
+    }
+}
@@ -0,0 +1,306 @@
+//! Storage backends for the URL map.
+//!
+//! Handlers talk to a [`Store`] trait object rather than a concrete map, so the
+//! backing store can be swapped at startup. Two implementations ship with the
+//! service: [`KnownUrls`](crate::KnownUrls), an in-memory map that is lost on
+//! restart, and [`SledStore`], an embedded key/value database that persists
+//! shortened URLs to disk.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::{LongURL, ShortUrl};
+
+/// Current wall-clock time in seconds since the Unix epoch.
+///
+/// Expiry deadlines are stored as absolute epoch seconds so they survive a
+/// restart (and a `sled` round-trip) unambiguously.
+pub fn now_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Outcome of resolving a short URL.
+///
+/// Distinguishes an expired link from an unknown one so `redirect` can answer
+/// `410 Gone` rather than `404 Not Found`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolved {
+    /// The short URL maps to this still-live long URL.
+    Found(LongURL),
+    /// The short URL existed but has passed its expiry deadline.
+    Expired,
+    /// The short URL was never registered.
+    Missing,
+}
+
+/// Access statistics for a single short URL, returned by the stats endpoint.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct UrlStats {
+    /// The long URL the short link expands to.
+    pub long_url: LongURL,
+    /// The full short URL (e.g. `http://short.fe/abc123`).
+    pub short_url: ShortUrl,
+    /// How many times the link has been resolved.
+    pub hits: u64,
+    /// Epoch seconds of the most recent resolution, if any.
+    pub last_access: Option<u64>,
+}
+
+/// Backing storage for the URL map.
+///
+/// Implementors provide the minimal set of operations the handlers need:
+/// looking an existing long URL up, recording a new mapping, and dumping every
+/// mapping for the debug endpoint. The methods are async so a backend can do
+/// real I/O (e.g. hit the disk) without blocking the executor.
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Returns the shortened form of `long`, if it has already been recorded.
+    async fn get(&self, long: &LongURL) -> Option<ShortUrl>;
+
+    /// Resolves a shortened URL back to the long URL it points at.
+    ///
+    /// `short` is the full short form (e.g. `http://short.fe/abc123`), matching what
+    /// [`insert`](Store::insert) was given. Expired links resolve to
+    /// [`Resolved::Expired`] rather than their target.
+    async fn resolve(&self, short: &ShortUrl) -> Resolved;
+
+    /// Records a `long` -> `short` mapping, keeping both lookup directions in
+    /// sync.
+    ///
+    /// `expiry` is an optional absolute deadline in epoch seconds after which
+    /// the link is considered gone.
+    async fn insert(&self, long: LongURL, short: ShortUrl, expiry: Option<u64>);
+
+    /// Records a hit against `short`: increments its counter and stamps the
+    /// last-access time. A no-op if the short URL is unknown.
+    async fn record_hit(&self, short: &ShortUrl);
+
+    /// Returns the access statistics for `short`, if it is known.
+    async fn stats(&self, short: &ShortUrl) -> Option<UrlStats>;
+
+    /// Removes every expired mapping and returns how many were swept.
+    async fn sweep(&self) -> usize;
+
+    /// Returns a snapshot of every known mapping.
+    async fn all(&self) -> HashMap<LongURL, ShortUrl>;
+}
+
+/// A [`Store`] backed by an embedded `sled` key/value database.
+///
+/// Both lookup directions are persisted so redirects stay O(1): long URLs are
+/// stored under an `l:` prefix pointing at their shortened variant, and short
+/// URLs under an `s:` prefix pointing back at the long URL. This mirrors the
+/// in-memory [`KnownUrls`](crate::KnownUrls) dual map while surviving restarts.
+#[derive(Debug, Clone)]
+pub struct SledStore {
+    db: sled::Db,
+}
+
+/// Prefix applied to long-URL keys in the `sled` database.
+const LONG_PREFIX: &[u8] = b"l:";
+/// Prefix applied to short-URL keys in the `sled` database.
+const SHORT_PREFIX: &[u8] = b"s:";
+
+/// Returns `key` with `prefix` prepended, for namespacing the two directions.
+fn prefixed(prefix: &[u8], key: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(prefix.len() + key.len());
+    out.extend_from_slice(prefix);
+    out.extend_from_slice(key.as_bytes());
+    out
+}
+
+/// The persisted fields of a short-URL mapping in `sled`.
+struct StoredRecord {
+    long: LongURL,
+    expiry: Option<u64>,
+    hits: u64,
+    last_access: Option<u64>,
+}
+
+/// Encodes a short-URL value as a tab-separated header line
+/// (`<expiry>\t<hits>\t<last_access>`) followed by the long URL. Empty header
+/// fields mean "unset".
+fn encode_value(record: &StoredRecord) -> Vec<u8> {
+    let opt = |v: Option<u64>| v.map(|n| n.to_string()).unwrap_or_default();
+    format!(
+        "{}\t{}\t{}\n{}",
+        opt(record.expiry),
+        record.hits,
+        opt(record.last_access),
+        record.long
+    )
+    .into_bytes()
+}
+
+/// Inverse of [`encode_value`]. Older single-field headers (just an expiry)
+/// decode with zeroed counters.
+fn decode_value(bytes: &[u8]) -> StoredRecord {
+    let value = String::from_utf8_lossy(bytes);
+    let (head, long) = value.split_once('\n').unwrap_or(("", &value));
+    let mut fields = head.split('\t');
+    let expiry = fields.next().and_then(|f| f.parse().ok());
+    let hits = fields.next().and_then(|f| f.parse().ok()).unwrap_or(0);
+    let last_access = fields.next().and_then(|f| f.parse().ok());
+    StoredRecord {
+        long: long.to_string(),
+        expiry,
+        hits,
+        last_access,
+    }
+}
+
+impl SledStore {
+    /// Opens (creating if necessary) a `sled` database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> sled::Result<Self> {
+        let db = sled::open(path)?;
+        Ok(Self { db })
+    }
+}
+
+#[async_trait]
+impl Store for SledStore {
+    async fn get(&self, long: &LongURL) -> Option<ShortUrl> {
+        match self.db.get(prefixed(LONG_PREFIX, long)) {
+            Ok(Some(value)) => Some(String::from_utf8_lossy(&value).into_owned()),
+            Ok(None) => None,
+            Err(e) => {
+                debug!(error = ?e, "sled lookup failed");
+                None
+            }
+        }
+    }
+
+    async fn resolve(&self, short: &ShortUrl) -> Resolved {
+        match self.db.get(prefixed(SHORT_PREFIX, short)) {
+            Ok(Some(value)) => {
+                let record = decode_value(&value);
+                match record.expiry {
+                    Some(deadline) if now_epoch() >= deadline => Resolved::Expired,
+                    _ => Resolved::Found(record.long),
+                }
+            }
+            Ok(None) => Resolved::Missing,
+            Err(e) => {
+                debug!(error = ?e, "sled reverse lookup failed");
+                Resolved::Missing
+            }
+        }
+    }
+
+    async fn insert(&self, long: LongURL, short: ShortUrl, expiry: Option<u64>) {
+        if let Err(e) = self.db.insert(prefixed(LONG_PREFIX, &long), short.as_bytes()) {
+            debug!(error = ?e, "sled insert failed");
+        }
+        let record = StoredRecord {
+            long,
+            expiry,
+            hits: 0,
+            last_access: None,
+        };
+        if let Err(e) = self
+            .db
+            .insert(prefixed(SHORT_PREFIX, &short), encode_value(&record))
+        {
+            debug!(error = ?e, "sled reverse insert failed");
+        }
+    }
+
+    async fn record_hit(&self, short: &ShortUrl) {
+        let key = prefixed(SHORT_PREFIX, short);
+        if let Ok(Some(value)) = self.db.get(&key) {
+            let mut record = decode_value(&value);
+            record.hits += 1;
+            record.last_access = Some(now_epoch());
+            if let Err(e) = self.db.insert(&key, encode_value(&record)) {
+                debug!(error = ?e, "sled hit update failed");
+            }
+        }
+    }
+
+    async fn stats(&self, short: &ShortUrl) -> Option<UrlStats> {
+        match self.db.get(prefixed(SHORT_PREFIX, short)) {
+            Ok(Some(value)) => {
+                let record = decode_value(&value);
+                Some(UrlStats {
+                    long_url: record.long,
+                    short_url: short.clone(),
+                    hits: record.hits,
+                    last_access: record.last_access,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    async fn sweep(&self) -> usize {
+        let now = now_epoch();
+        let mut removed = 0;
+        for (key, value) in self.db.scan_prefix(SHORT_PREFIX).filter_map(Result::ok) {
+            let record = decode_value(&value);
+            if matches!(record.expiry, Some(deadline) if now >= deadline) {
+                let _ = self.db.remove(&key);
+                let _ = self.db.remove(prefixed(LONG_PREFIX, &record.long));
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    async fn all(&self) -> HashMap<LongURL, ShortUrl> {
+        self.db
+            .scan_prefix(LONG_PREFIX)
+            .filter_map(Result::ok)
+            .map(|(key, value)| {
+                let long = String::from_utf8_lossy(&key[LONG_PREFIX.len()..]).into_owned();
+                (long, String::from_utf8_lossy(&value).into_owned())
+            })
+            .collect()
+    }
+}
+
+/// Selects which [`Store`] implementation to construct at startup.
+#[derive(Debug, Clone)]
+pub enum StorageBackend {
+    /// A volatile in-memory map, lost when the process exits.
+    InMemory,
+    /// A persistent `sled` database rooted at the given path.
+    Sled(PathBuf),
+}
+
+impl StorageBackend {
+    /// Reads the desired backend from the environment.
+    ///
+    /// `STORAGE_BACKEND=sled` selects the embedded database (rooted at
+    /// `SLED_PATH`, defaulting to `short-iron.db`); anything else falls back to
+    /// the in-memory map.
+    pub fn from_env() -> Self {
+        match std::env::var("STORAGE_BACKEND").as_deref() {
+            Ok("sled") => {
+                let path = std::env::var("SLED_PATH")
+                    .unwrap_or_else(|_| "short-iron.db".to_string());
+                StorageBackend::Sled(PathBuf::from(path))
+            }
+            _ => StorageBackend::InMemory,
+        }
+    }
+
+    /// Builds the selected [`Store`] as a shared trait object.
+    pub fn build(&self) -> std::io::Result<Arc<dyn Store>> {
+        match self {
+            StorageBackend::InMemory => Ok(Arc::new(crate::KnownUrls::default())),
+            StorageBackend::Sled(path) => {
+                let store = SledStore::open(path)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+                Ok(Arc::new(store))
+            }
+        }
+    }
+}